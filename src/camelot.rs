@@ -7,6 +7,7 @@ use serde_with::{DeserializeFromStr, SerializeDisplay};
 use thiserror::Error;
 
 mod search;
+mod theory;
 mod transition;
 
 #[derive(
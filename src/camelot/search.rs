@@ -4,7 +4,7 @@ use std::iter;
 use std::sync::LazyLock;
 
 use petgraph::{Graph, Undirected};
-use petgraph::prelude::{EdgeRef, NodeIndex};
+use petgraph::prelude::{EdgeIndex, EdgeRef, NodeIndex};
 
 use graphstuff::algo::clique::bron_kerbosch;
 use graphstuff::graph::SimpleGraph;
@@ -33,10 +33,15 @@ impl PartialOrd for NodeDistance {
 #[derive(Debug, Clone)]
 struct Path {
     cost: i32,
-    node: NodeIndex<u32>,
-    transition: Option<KeyTransition>,
     pub path: Vec<NodeIndex<u32>>,
     transition_path: Vec<KeyTransition>,
+    /// The specific edge traversed at each hop of `path`, in the same order
+    /// as `transition_path`. Kept alongside the node path because this graph
+    /// can have several parallel edges between the same two nodes, so
+    /// re-deriving "the edge between `path[i]` and `path[i+1]`" after the
+    /// fact (e.g. via `find_edge_undirected`) can resolve to the wrong
+    /// parallel edge.
+    edge_path: Vec<EdgeIndex<u32>>,
 }
 
 impl Eq for Path {}
@@ -61,7 +66,7 @@ impl PartialOrd for Path {
 
 /// Creates a graph representing the possible harmonic scale transitions in the camelot wheel
 pub fn make_scale_transition_graph() -> ScaleTransitions {
-    
+
     let mut graph = petgraph::Graph::<Key, KeyTransition, Undirected>::new_undirected();
 
     let nodes = make_standard_scale();
@@ -81,10 +86,63 @@ pub fn make_scale_transition_graph() -> ScaleTransitions {
         }
     }
 
+    let (distance, next) = floyd_warshall(&graph);
+
     ScaleTransitions {
         graph,
         index: scale_to_index,
+        distance,
+        next,
+    }
+}
+
+/// Precomputes all-pairs shortest transition distances (and next-hop
+/// successors) over the scale transition graph, so that
+/// [`ScaleTransitions::distance`] and [`ScaleTransitions::path_cached`] are
+/// O(1)/O(path length) lookups instead of a fresh Dijkstra run per call.
+fn floyd_warshall(
+    graph: &Graph<Key, KeyTransition, Undirected>,
+) -> (Vec<Vec<usize>>, Vec<Vec<Option<NodeIndex>>>) {
+    let n = graph.node_count();
+    let mut distance = vec![vec![usize::MAX; n]; n];
+    let mut next = vec![vec![None; n]; n];
+
+    for (i, row) in distance.iter_mut().enumerate() {
+        row[i] = 0;
+    }
+
+    for edge_id in graph.edge_indices() {
+        let (a, b) = graph.edge_endpoints(edge_id).unwrap();
+        let cost = graph.edge_weight(edge_id).unwrap().cost() as usize;
+
+        if cost < distance[a.index()][b.index()] {
+            distance[a.index()][b.index()] = cost;
+            distance[b.index()][a.index()] = cost;
+            next[a.index()][b.index()] = Some(b);
+            next[b.index()][a.index()] = Some(a);
+        }
     }
+
+    for k in 0..n {
+        for i in 0..n {
+            if distance[i][k] == usize::MAX {
+                continue;
+            }
+            for j in 0..n {
+                if distance[k][j] == usize::MAX {
+                    continue;
+                }
+
+                let through_k = distance[i][k] + distance[k][j];
+                if through_k < distance[i][j] {
+                    distance[i][j] = through_k;
+                    next[i][j] = next[i][k];
+                }
+            }
+        }
+    }
+
+    (distance, next)
 }
 
 #[test]
@@ -92,9 +150,80 @@ fn test_cliques() {
     dbg!(SCALE_TRANSITION_GRAPH.cliques());
 }
 
+#[test]
+fn test_path_cached_is_connected_and_matches_cached_distance() {
+    let scale_keys = make_standard_scale();
+    let a = scale_keys[3];
+    let b = scale_keys[17];
+
+    let path = ScaleTransitions::path_cached(a, b);
+    assert_eq!(path.first().copied(), Some(a));
+    assert_eq!(path.last().copied(), Some(b));
+
+    let graph = &SCALE_TRANSITION_GRAPH.graph;
+    let index = &SCALE_TRANSITION_GRAPH.index;
+    let total_cost: usize = path
+        .windows(2)
+        .map(|pair| {
+            let u = index[&pair[0]];
+            let v = index[&pair[1]];
+            let (edge_id, _) = graph.find_edge_undirected(u, v).unwrap();
+            graph.edge_weight(edge_id).unwrap().cost() as usize
+        })
+        .sum();
+
+    assert_eq!(total_cost, ScaleTransitions::distance(a, b));
+}
+
+#[test]
+fn test_multi_path_dijkstra_yields_distinct_nondecreasing_paths() {
+    let scale_keys = make_standard_scale();
+    let graph = &SCALE_TRANSITION_GRAPH.graph;
+    let index = &SCALE_TRANSITION_GRAPH.index;
+
+    let source = index[&scale_keys[0]];
+    let target = index[&scale_keys[12]];
+
+    let paths = multi_path_dijkstra(graph, source, target, 5);
+
+    assert!(paths.len() > 1, "expected more than one k-shortest path");
+    for window in paths.windows(2) {
+        assert!(window[0].cost <= window[1].cost, "paths must be non-decreasing in cost");
+        assert_ne!(window[0].path, window[1].path, "paths must be distinct");
+    }
+}
+
+#[test]
+fn test_order_set_keeps_pinned_start_through_two_opt_fallback() {
+    let keys = make_standard_scale();
+    assert!(keys.len() > HELD_KARP_MAX_KEYS, "test needs the 2-opt fallback path");
+
+    let start = keys[5];
+    let order = ScaleTransitions::order_set(&keys, Some(start));
+
+    assert_eq!(order.first().copied(), Some(start));
+}
+
+#[test]
+fn test_harmonic_loops_is_a_minimum_cycle_basis() {
+    let loops = SCALE_TRANSITION_GRAPH.harmonic_loops();
+    let edge_count = SCALE_TRANSITION_GRAPH.graph.edge_count();
+    let node_count = SCALE_TRANSITION_GRAPH.graph.node_count();
+
+    assert_eq!(loops.len(), edge_count + 1 - node_count);
+    for cycle in &loops {
+        assert!(cycle.len() >= 3, "a cycle needs at least 2 distinct edges plus the closing node");
+        assert_eq!(cycle.first(), cycle.last());
+    }
+}
+
 pub struct ScaleTransitions {
     index: HashMap<Key, NodeIndex>,
     graph: Graph<Key, KeyTransition, Undirected>,
+    /// `distance[a][b]`: cached all-pairs shortest transition cost, indexed by `NodeIndex::index()`.
+    distance: Vec<Vec<usize>>,
+    /// `next[a][b]`: the node to step to from `a` on the shortest path towards `b`.
+    next: Vec<Vec<Option<NodeIndex>>>,
 }
 
 impl ScaleTransitions {
@@ -121,57 +250,471 @@ impl ScaleTransitions {
 
         path
     }
+
+    /// Cached all-pairs harmonic transition distance between two keys, via
+    /// the Floyd-Warshall table computed alongside the graph.
+    pub fn distance(a: Key, b: Key) -> usize {
+        let transitions = &SCALE_TRANSITION_GRAPH;
+        let a_idx = transitions.index[&a].index();
+        let b_idx = transitions.index[&b].index();
+
+        transitions.distance[a_idx][b_idx]
+    }
+
+    /// Cached shortest path between two keys, reconstructed from the
+    /// Floyd-Warshall next-hop table instead of re-running Dijkstra.
+    pub fn path_cached(a: Key, b: Key) -> Vec<Key> {
+        let transitions = &SCALE_TRANSITION_GRAPH;
+        let a_idx = transitions.index[&a];
+        let b_idx = transitions.index[&b];
+
+        if transitions.distance[a_idx.index()][b_idx.index()] == usize::MAX {
+            return vec![];
+        }
+
+        let mut nodes = vec![a_idx];
+        let mut current = a_idx;
+        while current != b_idx {
+            current = transitions.next[current.index()][b_idx.index()].unwrap();
+            nodes.push(current);
+        }
+
+        nodes
+            .into_iter()
+            .map(|node| *transitions.graph.node_weight(node).unwrap())
+            .collect()
+    }
+
+    /// Returns a minimum cycle basis of the scale transition graph: the
+    /// independent cyclic key progressions (`edge_count - node_count + 1`
+    /// of them), each departing a key and returning to it, useful for
+    /// building repeating DJ-set turnarounds.
+    ///
+    /// Candidates are generated Horton-style: for every `(vertex, edge)`
+    /// pair, the shortest paths from the vertex to each of the edge's
+    /// endpoints, closed by the edge itself, form a candidate cycle through
+    /// that vertex. Per-edge candidates alone (one shortest alternate path
+    /// per edge) don't reach the full cycle space on this graph — it has
+    /// many parallel edges, which starves the candidate set of the cycles
+    /// that have to route through a third vertex — so every vertex is paired
+    /// with every edge here, per Horton's theorem that this superset always
+    /// contains a minimum cycle basis. The candidates are then run through
+    /// Gaussian elimination over GF(2), shortest first, to keep only the
+    /// independent ones.
+    pub fn harmonic_loops(&self) -> Vec<Vec<Key>> {
+        let graph = &self.graph;
+        let edge_count = graph.edge_count();
+        let node_count = graph.node_count();
+
+        let Some(dimension) = (edge_count + 1).checked_sub(node_count) else {
+            return vec![];
+        };
+        if dimension == 0 {
+            return vec![];
+        }
+
+        let mut candidates = Vec::new();
+        for vertex in graph.node_indices() {
+            for edge_id in graph.edge_indices() {
+                if let Some(candidate) = horton_candidate(graph, vertex, edge_id, edge_count) {
+                    candidates.push(candidate);
+                }
+            }
+        }
+
+        candidates.sort_by_key(|(weight, _, _)| *weight);
+
+        let mut basis: HashMap<usize, Vec<bool>> = HashMap::new();
+        let mut loops = Vec::new();
+
+        for (_, edges, nodes) in candidates {
+            let mut vector = edges;
+            while let Some(pivot) = vector.iter().position(|&bit| bit) {
+                match basis.get(&pivot) {
+                    Some(row) => {
+                        for (bit, row_bit) in vector.iter_mut().zip(row) {
+                            *bit ^= row_bit;
+                        }
+                    }
+                    None => {
+                        basis.insert(pivot, vector.clone());
+                        loops.push(
+                            nodes
+                                .iter()
+                                .map(|node| *graph.node_weight(*node).unwrap())
+                                .collect(),
+                        );
+                        break;
+                    }
+                }
+            }
+
+            if loops.len() == dimension {
+                break;
+            }
+        }
+
+        loops
+    }
+
+    /// Orders a batch of track keys to minimize total harmonic transition
+    /// cost between consecutive tracks, the core "sort my crate for a
+    /// smooth set" operation.
+    ///
+    /// Small sets (up to [`HELD_KARP_MAX_KEYS`]) are solved exactly as a
+    /// shortest Hamiltonian path via Held-Karp; larger ones fall back to a
+    /// nearest-neighbor seed improved with 2-opt. Pass `start` to pin the
+    /// opening track.
+    pub fn order_set(keys: &[Key], start: Option<Key>) -> Vec<Key> {
+        if keys.len() <= 1 {
+            return keys.to_vec();
+        }
+
+        let start_idx = start.and_then(|key| keys.iter().position(|&k| k == key));
+
+        let order = if keys.len() <= HELD_KARP_MAX_KEYS {
+            held_karp(keys, start_idx)
+        } else {
+            let seed = start_idx.unwrap_or(0);
+            two_opt(keys, nearest_neighbor(keys, seed), start_idx.is_some())
+        };
+
+        order.into_iter().map(|i| keys[i]).collect()
+    }
+}
+
+/// Above this many keys, the `2^n * n` Held-Karp table gets too large and
+/// [`ScaleTransitions::order_set`] falls back to a nearest-neighbor + 2-opt
+/// heuristic instead.
+const HELD_KARP_MAX_KEYS: usize = 13;
+
+/// Exact shortest Hamiltonian path over `keys` via Held-Karp dynamic
+/// programming, using the cached all-pairs transition distances. Returns
+/// the visiting order as indices into `keys`. When `start` is `None`, every
+/// key is tried as a starting point.
+fn held_karp(keys: &[Key], start: Option<usize>) -> Vec<usize> {
+    let n = keys.len();
+    let subsets = 1usize << n;
+
+    let mut dp = vec![vec![usize::MAX; n]; subsets];
+    let mut parent = vec![vec![None; n]; subsets];
+
+    match start {
+        Some(s) => dp[1 << s][s] = 0,
+        None => {
+            for i in 0..n {
+                dp[1 << i][i] = 0;
+            }
+        }
+    }
+
+    for mask in 0..subsets {
+        for i in 0..n {
+            if mask & (1 << i) == 0 || dp[mask][i] == usize::MAX {
+                continue;
+            }
+
+            for j in 0..n {
+                if mask & (1 << j) != 0 {
+                    continue;
+                }
+
+                let cost = dp[mask][i] + ScaleTransitions::distance(keys[i], keys[j]);
+                let next_mask = mask | (1 << j);
+                if cost < dp[next_mask][j] {
+                    dp[next_mask][j] = cost;
+                    parent[next_mask][j] = Some(i);
+                }
+            }
+        }
+    }
+
+    let full = subsets - 1;
+    let end = (0..n)
+        .min_by_key(|&i| dp[full][i])
+        .expect("keys is non-empty");
+
+    let mut order = Vec::with_capacity(n);
+    let mut mask = full;
+    let mut current = end;
+    loop {
+        order.push(current);
+        match parent[mask][current] {
+            Some(prev) => {
+                mask ^= 1 << current;
+                current = prev;
+            }
+            None => break,
+        }
+    }
+    order.reverse();
+
+    order
+}
+
+/// Greedy nearest-neighbor tour of `keys` starting at `start`, used to seed
+/// [`two_opt`] when the set is too large for [`held_karp`].
+fn nearest_neighbor(keys: &[Key], start: usize) -> Vec<usize> {
+    let n = keys.len();
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+
+    let mut current = start;
+    visited[current] = true;
+    order.push(current);
+
+    while order.len() < n {
+        let next = (0..n)
+            .filter(|&i| !visited[i])
+            .min_by_key(|&i| ScaleTransitions::distance(keys[current], keys[i]))
+            .expect("there is at least one unvisited key left");
+
+        visited[next] = true;
+        order.push(next);
+        current = next;
+    }
+
+    order
+}
+
+/// Repeatedly reverses segments of `order` while that shortens the total
+/// transition cost, until no single reversal improves it further. When
+/// `fixed_start` is set, position `0` is never included in a reversal, so
+/// a caller-pinned opening track stays in place.
+fn two_opt(keys: &[Key], mut order: Vec<usize>, fixed_start: bool) -> Vec<usize> {
+    let tour_cost = |order: &[usize]| -> usize {
+        order
+            .windows(2)
+            .map(|pair| ScaleTransitions::distance(keys[pair[0]], keys[pair[1]]))
+            .sum()
+    };
+
+    let first_movable = if fixed_start { 1 } else { 0 };
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+
+        for i in first_movable..order.len().saturating_sub(1) {
+            for j in (i + 1)..order.len() {
+                let mut candidate = order.clone();
+                candidate[i..=j].reverse();
+
+                if tour_cost(&candidate) < tour_cost(&order) {
+                    order = candidate;
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    order
 }
 
 pub static SCALE_TRANSITION_GRAPH: LazyLock<ScaleTransitions> =
     LazyLock::new(|| make_scale_transition_graph());
 
-/// Implementation of dijkstra's algorithm that returns the top n shortest paths
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct DijkstraEntry {
+    cost: i32,
+    node: NodeIndex<u32>,
+}
+
+impl Ord for DijkstraEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.cmp(&self.cost)
+    }
+}
+
+impl PartialOrd for DijkstraEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Single-source Dijkstra from `source` to `target`, ignoring any edge in
+/// `removed_edges` (checked in both directions, since the graph is
+/// undirected) and any node in `removed_nodes`. Also records the specific
+/// edge id traversed at each hop (in [`Path::edge_path`]), not just its
+/// `KeyTransition` label, since this graph can have several parallel edges
+/// between the same two nodes (e.g. a `ChangeIndex(7)` edge and a separate
+/// `ChangeIndex(-7)` edge connecting the same pair) and re-deriving "the
+/// edge between these two nodes" after the fact can resolve to the wrong
+/// one. Used as the building block for the loopless spur-path search in
+/// [`multi_path_dijkstra`] and the candidate-cycle search in
+/// [`ScaleTransitions::harmonic_loops`].
+fn dijkstra(
+    graph: &Graph<Key, KeyTransition, Undirected>,
+    source: NodeIndex<u32>,
+    target: NodeIndex<u32>,
+    removed_edges: &HashSet<(NodeIndex<u32>, NodeIndex<u32>)>,
+    removed_nodes: &HashSet<NodeIndex<u32>>,
+) -> Option<Path> {
+    let mut dist = HashMap::new();
+    let mut predecessor = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    dist.insert(source, 0);
+    heap.push(DijkstraEntry { cost: 0, node: source });
+
+    while let Some(DijkstraEntry { cost, node }) = heap.pop() {
+        if node == target {
+            break;
+        }
+        if cost > *dist.get(&node).unwrap_or(&i32::MAX) {
+            continue;
+        }
+
+        for edge in graph.edges(node) {
+            let neighbor = edge.target();
+            if removed_nodes.contains(&neighbor)
+                || removed_edges.contains(&(node, neighbor))
+                || removed_edges.contains(&(neighbor, node))
+            {
+                continue;
+            }
+
+            let transition = *edge.weight();
+            let next_cost = cost + transition.cost();
+            if next_cost < *dist.get(&neighbor).unwrap_or(&i32::MAX) {
+                dist.insert(neighbor, next_cost);
+                predecessor.insert(neighbor, (node, transition, edge.id()));
+                heap.push(DijkstraEntry { cost: next_cost, node: neighbor });
+            }
+        }
+    }
+
+    let cost = *dist.get(&target)?;
+
+    let mut path = vec![target];
+    let mut transition_path = vec![];
+    let mut edge_path = vec![];
+    let mut current = target;
+    while current != source {
+        let (prev_node, transition, edge_id) = predecessor[&current];
+        transition_path.push(transition);
+        edge_path.push(edge_id);
+        path.push(prev_node);
+        current = prev_node;
+    }
+    path.reverse();
+    transition_path.reverse();
+    edge_path.reverse();
+
+    Some(Path { cost, path, transition_path, edge_path })
+}
+
+/// Builds one Horton-style cycle-basis candidate: the shortest path from
+/// `vertex` to each endpoint of `edge_id`, closed by that edge. Returns
+/// `None` when either endpoint is unreachable from `vertex`, or when the two
+/// shortest paths share a node other than `vertex` (or either already routes
+/// through `edge_id` itself) — in which case the result wouldn't be a simple
+/// cycle through `edge_id`. On success, returns `(weight, bit-vector over
+/// edges, cycle as a node sequence)`, matching the shape
+/// [`ScaleTransitions::harmonic_loops`] feeds into Gaussian elimination.
+fn horton_candidate(
+    graph: &Graph<Key, KeyTransition, Undirected>,
+    vertex: NodeIndex<u32>,
+    edge_id: EdgeIndex<u32>,
+    edge_count: usize,
+) -> Option<(i32, Vec<bool>, Vec<NodeIndex<u32>>)> {
+    let (x, y) = graph.edge_endpoints(edge_id).unwrap();
+    let edge_cost = graph.edge_weight(edge_id).unwrap().cost();
+
+    let to_x = dijkstra(graph, vertex, x, &HashSet::new(), &HashSet::new())?;
+    let to_y = dijkstra(graph, vertex, y, &HashSet::new(), &HashSet::new())?;
+
+    let to_x_nodes: HashSet<_> = to_x.path[1..].iter().copied().collect();
+    let shares_a_node = to_y.path[1..].iter().any(|node| to_x_nodes.contains(node));
+    if shares_a_node || to_x.edge_path.contains(&edge_id) || to_y.edge_path.contains(&edge_id) {
+        return None;
+    }
+
+    let mut edges = vec![false; edge_count];
+    edges[edge_id.index()] = true;
+    for edge in to_x.edge_path.iter().chain(&to_y.edge_path) {
+        edges[edge.index()] = true;
+    }
+
+    let mut nodes = to_x.path.clone();
+    nodes.push(y);
+    nodes.extend(to_y.path.iter().rev().skip(1).copied());
+
+    Some((to_x.cost + edge_cost + to_y.cost, edges, nodes))
+}
+
+/// Returns the top `n` loopless shortest paths from `source` to `target`,
+/// using Yen's algorithm layered on top of [`dijkstra`]. `A` holds the
+/// already-accepted paths, `B` the candidate spur paths considered for the
+/// next slot.
 fn multi_path_dijkstra(
     graph: &Graph<Key, KeyTransition, Undirected>,
     source: NodeIndex<u32>,
     target: NodeIndex<u32>,
     n: usize,
 ) -> Vec<Path> {
-    let mut min_heap = BinaryHeap::new();
-    let mut paths = Vec::new();
+    let mut a = Vec::new();
 
-    min_heap.push(Path {
-        cost: 0,
-        node: source,
-        transition: None,
-        path: vec![],
-        transition_path: vec![],
-    });
+    let Some(shortest) = dijkstra(graph, source, target, &HashSet::new(), &HashSet::new()) else {
+        return a;
+    };
+    a.push(shortest);
 
-    while let Some(mut path) = min_heap.pop() {
-        path.path.push(path.node);
+    let mut b: BinaryHeap<Path> = BinaryHeap::new();
 
-        if let Some(transition) = path.transition {
-            path.transition_path.push(transition);
-        }
+    while a.len() < n {
+        let prev_path = a.last().unwrap().clone();
 
-        if path.node == target {
-            paths.push(path.clone());
-            if paths.len() >= n {
-                break;
+        for i in 0..prev_path.path.len() - 1 {
+            let spur_node = prev_path.path[i];
+            let root_nodes = &prev_path.path[..=i];
+
+            let mut removed_edges = HashSet::new();
+            for path in &a {
+                if path.path.len() > i && path.path[..=i] == *root_nodes {
+                    removed_edges.insert((path.path[i], path.path[i + 1]));
+                    removed_edges.insert((path.path[i + 1], path.path[i]));
+                }
             }
-        }
 
-        for edge in graph.edges(path.node) {
-            let neighbor = edge.target();
-            let weight = graph.edge_weight(edge.id()).unwrap();
-            min_heap.push(Path {
-                cost: path.cost + 1,
-                node: neighbor,
-                transition: Some(*weight),
-                transition_path: path.transition_path.clone(),
-                path: path.path.clone(),
-            });
+            let removed_nodes: HashSet<_> = prev_path.path[..i].iter().copied().collect();
+
+            let Some(spur_path) = dijkstra(graph, spur_node, target, &removed_edges, &removed_nodes)
+            else {
+                continue;
+            };
+
+            let mut path = prev_path.path[..i].to_vec();
+            path.extend(&spur_path.path);
+
+            let mut transition_path = prev_path.transition_path[..i].to_vec();
+            transition_path.extend(&spur_path.transition_path);
+
+            let mut edge_path = prev_path.edge_path[..i].to_vec();
+            edge_path.extend(&spur_path.edge_path);
+
+            let root_cost: i32 = prev_path.transition_path[..i].iter().map(KeyTransition::cost).sum();
+            let candidate = Path {
+                cost: root_cost + spur_path.cost,
+                path,
+                transition_path,
+                edge_path,
+            };
+
+            let already_known = a.iter().any(|p| p.path == candidate.path)
+                || b.iter().any(|p| p.path == candidate.path);
+            if !already_known {
+                b.push(candidate);
+            }
         }
+
+        let Some(next) = b.pop() else {
+            break;
+        };
+        a.push(next);
     }
 
-    paths
+    a
 }
 
 /// Returns all possible harmonic transitions from a given key
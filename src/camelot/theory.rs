@@ -0,0 +1,183 @@
+use std::fmt::{Display, Formatter};
+
+use crate::camelot::{mod_cyclic, scale, Key, Mode};
+
+/// A pitch class, spelled with sharps. Every tonic on the Camelot wheel
+/// resolves to one of these twelve.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Note {
+    C,
+    CSharp,
+    D,
+    DSharp,
+    E,
+    F,
+    FSharp,
+    G,
+    GSharp,
+    A,
+    ASharp,
+    B,
+}
+
+impl Display for Note {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Note::C => "C",
+                Note::CSharp => "C#",
+                Note::D => "D",
+                Note::DSharp => "D#",
+                Note::E => "E",
+                Note::F => "F",
+                Note::FSharp => "F#",
+                Note::G => "G",
+                Note::GSharp => "G#",
+                Note::A => "A",
+                Note::ASharp => "A#",
+                Note::B => "B",
+            }
+        )
+    }
+}
+
+const NOTES: [Note; 12] = [
+    Note::C,
+    Note::CSharp,
+    Note::D,
+    Note::DSharp,
+    Note::E,
+    Note::F,
+    Note::FSharp,
+    Note::G,
+    Note::GSharp,
+    Note::A,
+    Note::ASharp,
+    Note::B,
+];
+
+fn note_from_pitch_class(pitch_class: usize) -> Note {
+    NOTES[pitch_class % 12]
+}
+
+/// Pitch class of the minor tonic at wheel index `0` (`1A` is G#/Ab minor).
+/// The major side of the wheel sits a minor third higher (`1B` is B major).
+const MINOR_TONIC_OFFSET: isize = 8;
+const MAJOR_TONIC_OFFSET: isize = MINOR_TONIC_OFFSET + 3;
+
+/// Semitone offsets of the seven natural minor scale degrees.
+const MINOR_SCALE: [isize; 7] = [0, 2, 3, 5, 7, 8, 10];
+/// Semitone offsets of the seven major scale degrees.
+const MAJOR_SCALE: [isize; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// Quality of a stacked-thirds triad.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ChordQuality {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Chord {
+    pub root: Note,
+    pub quality: ChordQuality,
+}
+
+impl Display for Chord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let suffix = match self.quality {
+            ChordQuality::Major => "",
+            ChordQuality::Minor => "m",
+            ChordQuality::Diminished => "dim",
+            ChordQuality::Augmented => "aug",
+        };
+        write!(f, "{}{}", self.root, suffix)
+    }
+}
+
+impl Key {
+    /// Maps the wheel index to a pitch class: the Camelot numbering walks
+    /// the circle of fifths, so each step of `tonic` moves the pitch class
+    /// by a perfect fifth (7 semitones), offset to the wheel's reference
+    /// key for the relevant mode.
+    pub fn tonic_pitch_class(&self) -> usize {
+        let offset = match self.mode {
+            Mode::Minor => MINOR_TONIC_OFFSET,
+            Mode::Major => MAJOR_TONIC_OFFSET,
+        };
+
+        mod_cyclic(self.tonic as isize * 7 + offset, 12) as usize
+    }
+
+    /// The tonic note of this key, e.g. `8A` (A minor) maps to `A`.
+    pub fn tonic_note(&self) -> Note {
+        note_from_pitch_class(self.tonic_pitch_class())
+    }
+
+    /// The seven diatonic notes of this key's scale (natural minor or
+    /// major, depending on [`Mode`]), in ascending order from the tonic.
+    pub fn scale_notes(&self) -> Vec<Note> {
+        let tonic_pc = self.tonic_pitch_class() as isize;
+        let intervals = match self.mode {
+            Mode::Minor => MINOR_SCALE,
+            Mode::Major => MAJOR_SCALE,
+        };
+
+        intervals
+            .iter()
+            .map(|interval| note_from_pitch_class(mod_cyclic(tonic_pc + interval, 12) as usize))
+            .collect()
+    }
+
+    /// The seven diatonic triads of this key, built by stacking thirds on
+    /// each degree of [`Key::scale_notes`].
+    pub fn diatonic_triads(&self) -> Vec<Chord> {
+        let tonic_pc = self.tonic_pitch_class() as isize;
+        let intervals = match self.mode {
+            Mode::Minor => MINOR_SCALE,
+            Mode::Major => MAJOR_SCALE,
+        };
+
+        // Unreduced semitone position of a scale degree, so thirds stacked
+        // past the seventh degree still measure the right distance apart.
+        let degree_pitch = |degree: usize| -> isize {
+            tonic_pc + intervals[degree % 7] + 12 * (degree / 7) as isize
+        };
+
+        (0..7)
+            .map(|degree| {
+                let root = degree_pitch(degree);
+                let third = degree_pitch(degree + 2);
+                let fifth = degree_pitch(degree + 4);
+
+                let quality = match (third - root, fifth - third) {
+                    (4, 3) => ChordQuality::Major,
+                    (3, 4) => ChordQuality::Minor,
+                    (3, 3) => ChordQuality::Diminished,
+                    (4, 4) => ChordQuality::Augmented,
+                    _ => unreachable!("diatonic thirds are always 3 or 4 semitones apart"),
+                };
+
+                Chord {
+                    root: note_from_pitch_class(mod_cyclic(root, 12) as usize),
+                    quality,
+                }
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_8a_is_a_minor_with_natural_notes() {
+    let key = scale(7, Mode::Minor);
+
+    assert_eq!(key.tonic_note(), Note::A);
+    assert_eq!(
+        key.scale_notes(),
+        vec![Note::A, Note::B, Note::C, Note::D, Note::E, Note::F, Note::G]
+    );
+}
@@ -24,6 +24,40 @@ pub const fn harmonic_transitions() -> [KeyTransition; 10] {
     ]
 }
 
+/// Harmonic "tension" cost of each transition, lowest first.
+///
+/// Moving along the relative major/minor axis (`Vertical`) or seven wheel
+/// indices (`ChangeIndex(±7)`) is cheapest; single-index steps and
+/// `Diagonal` cost a little more, jumping two indices more again, and the
+/// `MajorToMinor` and `FlatToMinor` moves cost the most. Path searches in
+/// `search` (`multi_path_dijkstra` and the cached all-pairs distances)
+/// always price edges through `KeyTransition::cost`, i.e. this table; they
+/// take no other cost table.
+pub const TRANSITION_COSTS: [(KeyTransition, i32); 10] = [
+    (KeyTransition::Vertical, 1),
+    (KeyTransition::ChangeIndex(7), 1),
+    (KeyTransition::ChangeIndex(-7), 1),
+    (KeyTransition::Diagonal, 2),
+    (KeyTransition::ChangeIndex(1), 2),
+    (KeyTransition::ChangeIndex(-1), 2),
+    (KeyTransition::ChangeIndex(2), 4),
+    (KeyTransition::ChangeIndex(-2), 4),
+    (KeyTransition::MajorToMinor, 5),
+    (KeyTransition::FlatToMinor, 6),
+];
+
+impl KeyTransition {
+    /// The harmonic tension cost of this transition, looked up in
+    /// [`TRANSITION_COSTS`]. Unlisted transitions default to `1`.
+    pub fn cost(&self) -> i32 {
+        TRANSITION_COSTS
+            .iter()
+            .find(|(transition, _)| transition == self)
+            .map(|(_, cost)| *cost)
+            .unwrap_or(1)
+    }
+}
+
 pub fn make_transition(scale: Key, transition: KeyTransition) -> Key {
     match transition {
         KeyTransition::Vertical => scale.swap_kind(),